@@ -0,0 +1,258 @@
+// ============================================================================
+// desktop/cvere-vm/src/gdb.rs
+// GDB remote-serial-protocol stub for the CVERE VM
+// ============================================================================
+
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::registers::RegisterFile;
+
+/// Anything the stub can drive: register access, memory peek/poke, and a
+/// single-step of the execution loop. Implemented by the VM.
+pub trait GdbTarget {
+    fn registers(&self) -> &RegisterFile;
+    fn registers_mut(&mut self) -> &mut RegisterFile;
+    fn peek(&self, addr: u16) -> u8;
+    fn poke(&mut self, addr: u16, value: u8);
+    /// Execute one instruction; returns `false` once the VM has halted.
+    fn step(&mut self) -> bool;
+}
+
+// The protocol exposes the 16 GP registers followed by PC, SP, LR, SR.
+const GP_COUNT: usize = 16;
+const REG_COUNT: usize = GP_COUNT + 4;
+
+/// A GDB remote stub that speaks the serial protocol over TCP.
+///
+/// Attach with `target remote :<port>` from `gdb`/`lldb` to set breakpoints,
+/// single-step, read/write registers, and peek/poke memory. Software
+/// breakpoints (`Z0`/`z0`) are tracked in a set the continue loop consults
+/// before each step.
+pub struct GdbStub {
+    breakpoints: HashSet<u16>,
+}
+
+impl GdbStub {
+    pub fn new() -> Self {
+        GdbStub {
+            breakpoints: HashSet::new(),
+        }
+    }
+
+    /// Listen on `addr` and serve a single debugging session against `target`.
+    pub fn serve<T: GdbTarget>(&mut self, addr: &str, target: &mut T) -> std::io::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        let (mut stream, _) = listener.accept()?;
+        self.session(&mut stream, target)
+    }
+
+    fn session<T: GdbTarget>(
+        &mut self,
+        stream: &mut TcpStream,
+        target: &mut T,
+    ) -> std::io::Result<()> {
+        let mut buf = [0u8; 1];
+        let mut packet = Vec::new();
+        loop {
+            if stream.read(&mut buf)? == 0 {
+                return Ok(()); // client disconnected
+            }
+            match buf[0] {
+                b'+' | b'-' => {} // ack / retransmit request: ignore
+                0x03 => {
+                    // Ctrl-C interrupt: report a SIGTRAP stop.
+                    self.send(stream, b"S05")?;
+                }
+                b'$' => {
+                    packet.clear();
+                    loop {
+                        stream.read_exact(&mut buf)?;
+                        if buf[0] == b'#' {
+                            break;
+                        }
+                        packet.push(buf[0]);
+                    }
+                    // Read and discard the two-digit checksum.
+                    let mut cksum = [0u8; 2];
+                    stream.read_exact(&mut cksum)?;
+                    stream.write_all(b"+")?; // acknowledge receipt
+
+                    let reply = self.dispatch(&packet, target);
+                    self.send(stream, &reply)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Handle one decoded packet body and produce a reply body.
+    fn dispatch<T: GdbTarget>(&mut self, packet: &[u8], target: &mut T) -> Vec<u8> {
+        match packet.first() {
+            Some(b'?') => b"S05".to_vec(),
+            Some(b'g') => Self::read_registers(target.registers()),
+            Some(b'G') => {
+                Self::write_registers(target.registers_mut(), &packet[1..]);
+                b"OK".to_vec()
+            }
+            Some(b'm') => Self::read_memory(packet, target),
+            Some(b'M') => Self::write_memory(packet, target),
+            Some(b's') => {
+                target.step();
+                b"S05".to_vec()
+            }
+            Some(b'c') => {
+                self.resume(target);
+                b"S05".to_vec()
+            }
+            Some(b'Z') => self.set_breakpoint(packet, true),
+            Some(b'z') => self.set_breakpoint(packet, false),
+            Some(b'q') => b"".to_vec(),
+            _ => b"".to_vec(),
+        }
+    }
+
+    /// Continue until a breakpoint is hit or the VM halts.
+    fn resume<T: GdbTarget>(&mut self, target: &mut T) {
+        loop {
+            if self.breakpoints.contains(&target.registers().pc) {
+                return;
+            }
+            if !target.step() {
+                return;
+            }
+        }
+    }
+
+    fn set_breakpoint(&mut self, packet: &[u8], insert: bool) -> Vec<u8> {
+        // Format: Z0,<addr>,<kind> — only software breakpoints (type 0).
+        let body = String::from_utf8_lossy(&packet[1..]);
+        let mut parts = body.split(',');
+        let kind = parts.next();
+        let addr = parts.next().and_then(|a| u16::from_str_radix(a, 16).ok());
+        match (kind, addr) {
+            (Some("0"), Some(addr)) => {
+                if insert {
+                    self.breakpoints.insert(addr);
+                } else {
+                    self.breakpoints.remove(&addr);
+                }
+                b"OK".to_vec()
+            }
+            _ => b"".to_vec(), // unsupported breakpoint type
+        }
+    }
+
+    fn read_registers(regs: &RegisterFile) -> Vec<u8> {
+        let mut out = Vec::with_capacity(REG_COUNT * 4);
+        for i in 0..GP_COUNT {
+            Self::push_reg(&mut out, regs.read_gp(i as u8));
+        }
+        Self::push_reg(&mut out, regs.pc);
+        Self::push_reg(&mut out, regs.sp);
+        Self::push_reg(&mut out, regs.lr);
+        Self::push_reg(&mut out, regs.sr);
+        out
+    }
+
+    fn write_registers(regs: &mut RegisterFile, body: &[u8]) {
+        let values: Vec<u16> = body
+            .chunks(4)
+            .filter_map(Self::parse_reg)
+            .collect();
+        for (i, &v) in values.iter().take(GP_COUNT).enumerate() {
+            regs.write_gp(i as u8, v);
+        }
+        if let Some(&v) = values.get(GP_COUNT) {
+            regs.pc = v;
+        }
+        if let Some(&v) = values.get(GP_COUNT + 1) {
+            regs.sp = v;
+        }
+        if let Some(&v) = values.get(GP_COUNT + 2) {
+            regs.lr = v;
+        }
+        if let Some(&v) = values.get(GP_COUNT + 3) {
+            regs.sr = v;
+        }
+    }
+
+    fn read_memory<T: GdbTarget>(packet: &[u8], target: &T) -> Vec<u8> {
+        // Format: m<addr>,<len>
+        let body = String::from_utf8_lossy(&packet[1..]);
+        let mut parts = body.split(',');
+        let addr = parts.next().and_then(|a| u16::from_str_radix(a, 16).ok());
+        let len = parts.next().and_then(|l| u16::from_str_radix(l, 16).ok());
+        match (addr, len) {
+            (Some(addr), Some(len)) => {
+                let mut out = Vec::with_capacity(len as usize * 2);
+                for i in 0..len {
+                    Self::push_byte(&mut out, target.peek(addr.wrapping_add(i)));
+                }
+                out
+            }
+            _ => b"E01".to_vec(),
+        }
+    }
+
+    fn write_memory<T: GdbTarget>(packet: &[u8], target: &mut T) -> Vec<u8> {
+        // Format: M<addr>,<len>:<hex bytes>
+        let body = String::from_utf8_lossy(&packet[1..]);
+        let (head, data) = match body.split_once(':') {
+            Some(parts) => parts,
+            None => return b"E01".to_vec(),
+        };
+        let mut parts = head.split(',');
+        let addr = parts.next().and_then(|a| u16::from_str_radix(a, 16).ok());
+        match addr {
+            Some(addr) => {
+                let bytes = data.as_bytes();
+                for (i, pair) in bytes.chunks(2).enumerate() {
+                    if let Some(byte) = Self::parse_byte(pair) {
+                        target.poke(addr.wrapping_add(i as u16), byte);
+                    }
+                }
+                b"OK".to_vec()
+            }
+            None => b"E01".to_vec(),
+        }
+    }
+
+    /// Frame `body` with `$...#cc` and a two-digit checksum, then transmit.
+    fn send(&self, stream: &mut TcpStream, body: &[u8]) -> std::io::Result<()> {
+        let checksum: u8 = body.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        stream.write_all(b"$")?;
+        stream.write_all(body)?;
+        write!(stream, "#{:02x}", checksum)?;
+        stream.flush()
+    }
+
+    fn push_reg(out: &mut Vec<u8>, value: u16) {
+        // GDB expects little-endian byte order.
+        Self::push_byte(out, (value & 0xFF) as u8);
+        Self::push_byte(out, (value >> 8) as u8);
+    }
+
+    fn push_byte(out: &mut Vec<u8>, byte: u8) {
+        out.extend_from_slice(format!("{:02x}", byte).as_bytes());
+    }
+
+    fn parse_reg(chunk: &[u8]) -> Option<u16> {
+        // A `chunks(4)` remainder can be shorter than a full register; drop it
+        // rather than slicing out of bounds on a malformed packet.
+        if chunk.len() < 4 {
+            return None;
+        }
+        let low = Self::parse_byte(&chunk[0..2])? as u16;
+        let high = Self::parse_byte(&chunk[2..4])? as u16;
+        Some((high << 8) | low)
+    }
+
+    fn parse_byte(pair: &[u8]) -> Option<u8> {
+        if pair.len() < 2 {
+            return None;
+        }
+        u8::from_str_radix(std::str::from_utf8(&pair[0..2]).ok()?, 16).ok()
+    }
+}
@@ -0,0 +1,97 @@
+// ============================================================================
+// desktop/cvere-vm/src/mmu.rs
+// Paging MMU for the CVERE VM
+// ============================================================================
+
+use crate::registers::{PrivilegeLevel, RegisterFile};
+
+/// Kind of access being translated. Used to pick the permission bit to check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccessKind {
+    Fetch,  // Instruction fetch
+    Read,   // Data load
+    Write,  // Data store
+}
+
+/// Trap cause codes raised by translation, matching the CSR trap subsystem.
+pub const CAUSE_PAGE_FAULT: u16 = 0xC;
+
+// Page-table entry layout (one 16-bit word in guest memory):
+//   bits [15:4]  physical frame number (only bits [7:4] are used: the
+//                16-bit physical space holds 16 frames of 4 KiB)
+//   bit  3       user-accessible
+//   bit  2       executable
+//   bit  1       writable
+//   bit  0       valid (readable when valid)
+const PTE_VALID: u16 = 1 << 0;
+const PTE_WRITE: u16 = 1 << 1;
+const PTE_EXEC: u16 = 1 << 2;
+const PTE_USER: u16 = 1 << 3;
+const PTE_FRAME_SHIFT: u16 = 4;
+
+// Single-level split: 4-bit page index, 12-bit offset.
+const OFFSET_BITS: u16 = 12;
+const OFFSET_MASK: u16 = (1 << OFFSET_BITS) - 1;
+
+/// A paging memory-management unit driven by the register file's `ptbr`.
+///
+/// Translation is bypassed entirely while `ptbr == 0` (bare mode) so programs
+/// written before paging existed keep working. When a page-table entry is
+/// invalid or the access violates its permissions for the current privilege,
+/// `translate` raises a page fault through `RegisterFile`, recording the
+/// faulting virtual address in `tval`.
+pub struct Mmu;
+
+impl Mmu {
+    /// Translate a virtual address for the given access, or raise a page fault.
+    ///
+    /// Returns `Some(physical)` on success and `None` when a fault was raised
+    /// (the caller should abort the access and resume at the trap vector).
+    pub fn translate(
+        regs: &mut RegisterFile,
+        memory: &[u8],
+        vaddr: u16,
+        access: AccessKind,
+    ) -> Option<u16> {
+        // Bare mode: identity-map while no page table is installed.
+        if regs.ptbr == 0 {
+            return Some(vaddr);
+        }
+
+        let page = (vaddr >> OFFSET_BITS) & 0xF;
+        let offset = vaddr & OFFSET_MASK;
+
+        // Each entry is a 16-bit word; index the table rooted at `ptbr`.
+        let pte_addr = regs.ptbr.wrapping_add(page.wrapping_mul(2)) as usize;
+        if pte_addr + 1 >= memory.len() {
+            regs.raise_privilege_on_exception(CAUSE_PAGE_FAULT, vaddr);
+            return None;
+        }
+        let pte = (memory[pte_addr] as u16) | ((memory[pte_addr + 1] as u16) << 8);
+
+        if !Self::permitted(pte, access, regs.privilege) {
+            regs.raise_privilege_on_exception(CAUSE_PAGE_FAULT, vaddr);
+            return None;
+        }
+
+        let frame = pte >> PTE_FRAME_SHIFT;
+        Some((frame << OFFSET_BITS) | offset)
+    }
+
+    /// Check that `pte` is valid and allows `access` from `privilege`.
+    fn permitted(pte: u16, access: AccessKind, privilege: PrivilegeLevel) -> bool {
+        if pte & PTE_VALID == 0 {
+            return false;
+        }
+        // User code may only touch user-accessible pages; Kernel/Supervisor
+        // are unrestricted by the U bit.
+        if privilege == PrivilegeLevel::User && pte & PTE_USER == 0 {
+            return false;
+        }
+        match access {
+            AccessKind::Read => true, // valid implies readable
+            AccessKind::Write => pte & PTE_WRITE != 0,
+            AccessKind::Fetch => pte & PTE_EXEC != 0,
+        }
+    }
+}
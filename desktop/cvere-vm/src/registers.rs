@@ -12,6 +12,7 @@ pub enum PrivilegeLevel {
 }
 
 /// Register file with 16 general-purpose registers and special registers
+#[derive(Clone)]
 pub struct RegisterFile {
     // General purpose registers R0-RF
     gp_regs: [u16; 16],
@@ -32,6 +33,16 @@ pub struct RegisterFile {
     pub exception_handler: u16,  // Exception handler address
     pub saved_pc: u16,           // Saved PC on exception
     pub saved_sr: u16,           // Saved SR on exception
+
+    // Control & status registers for traps (modeled on RISC-V CSRs)
+    pub cause: u16,              // Why we trapped (cause code)
+    pub tval: u16,              // Faulting value / address
+    pub kernel_tvec: u16,       // Kernel trap vector (Ring 0)
+    pub supervisor_tvec: u16,   // Supervisor trap vector (Ring 1)
+    pub trap_deleg: u16,        // Per-cause delegation bitmask: User -> Supervisor
+
+    // Memory management
+    pub ptbr: u16,              // Page-table base register (0 = bare mode)
 }
 
 impl RegisterFile {
@@ -51,9 +62,27 @@ impl RegisterFile {
             exception_handler: 0x0010,  // Default exception handler
             saved_pc: 0,
             saved_sr: 0,
+
+            cause: 0,
+            tval: 0,
+            kernel_tvec: 0x0010,    // Defaults to the legacy exception handler
+            supervisor_tvec: 0,
+            trap_deleg: 0,
+
+            ptbr: 0,
         }
     }
 
+    // Bits [9:8] of the status register hold the privilege level that was
+    // active before the most recent trap, so the return path can restore it.
+    const SR_PP_SHIFT: u16 = 8;
+    const SR_PP_MASK: u16 = 0b11 << Self::SR_PP_SHIFT;
+
+    /// Return the status register with `prev` packed into its previous-privilege field.
+    fn with_prev_privilege(&self, prev: PrivilegeLevel) -> u16 {
+        (self.sr & !Self::SR_PP_MASK) | ((prev as u16) << Self::SR_PP_SHIFT)
+    }
+
     /// Read from general purpose register
     pub fn read_gp(&self, reg: u8) -> u16 {
         if reg >= 16 {
@@ -107,23 +136,71 @@ impl RegisterFile {
         self.privilege = target;
     }
 
-    /// PROMOTION: Used only by hardware exceptions/interrupts
-    pub fn raise_privilege_on_exception(&mut self) {
-        // 1. Save state
+    /// PROMOTION: Used only by hardware exceptions/interrupts.
+    ///
+    /// `cause` identifies the trap and `tval` carries the faulting value or
+    /// address. If the current privilege is User and the cause's bit is set in
+    /// `trap_deleg`, the trap is delivered to Supervisor (`supervisor_tvec`);
+    /// otherwise it is delivered to Kernel (`kernel_tvec`). The previous
+    /// privilege is packed into the saved status register for `return_from_trap`.
+    pub fn raise_privilege_on_exception(&mut self, cause: u16, tval: u16) {
+        // 1. Record why we trapped and the offending value/address.
+        self.cause = cause;
+        self.tval = tval;
+
+        // 2. Decide the delivery target. A User fault whose cause bit is
+        //    delegated is handled by Supervisor; everything else by Kernel.
+        let to_supervisor = self.privilege == PrivilegeLevel::User
+            && (self.trap_deleg & (1u16 << (cause & 0xF))) != 0;
+
+        // 3. Save state, stashing the outgoing privilege in the status register.
         self.saved_pc = self.pc;
-        self.saved_sr = self.sr;
-        
-        // 2. Save current SP
+        self.saved_sr = self.with_prev_privilege(self.privilege);
+
+        // 4. Bank the outgoing stack pointer.
         match self.privilege {
+            PrivilegeLevel::Kernel => self.kernel_sp = self.sp,
             PrivilegeLevel::Supervisor => self.supervisor_sp = self.sp,
             PrivilegeLevel::User => self.user_sp = self.sp,
-            _ => {} // Already in Kernel
         }
 
-        // 3. Jump to Kernel mode (Ring 0) to handle the event
-        self.privilege = PrivilegeLevel::Kernel;
-        self.sp = self.kernel_sp;
-        self.pc = self.exception_handler;
+        // 5. Enter the handler.
+        if to_supervisor {
+            self.privilege = PrivilegeLevel::Supervisor;
+            self.sp = self.supervisor_sp;
+            self.pc = self.supervisor_tvec;
+        } else {
+            self.privilege = PrivilegeLevel::Kernel;
+            self.sp = self.kernel_sp;
+            self.pc = self.kernel_tvec;
+        }
+    }
+
+    /// Return from a trap handler: restore the pre-trap privilege (from the
+    /// previous-privilege field of the saved status register), stack pointer,
+    /// status flags, and PC.
+    pub fn return_from_trap(&mut self) {
+        let prev = match (self.saved_sr & Self::SR_PP_MASK) >> Self::SR_PP_SHIFT {
+            x if x == PrivilegeLevel::Supervisor as u16 => PrivilegeLevel::Supervisor,
+            x if x == PrivilegeLevel::User as u16 => PrivilegeLevel::User,
+            _ => PrivilegeLevel::Kernel,
+        };
+
+        // Bank the handler's stack pointer before switching back.
+        match self.privilege {
+            PrivilegeLevel::Kernel => self.kernel_sp = self.sp,
+            PrivilegeLevel::Supervisor => self.supervisor_sp = self.sp,
+            PrivilegeLevel::User => self.user_sp = self.sp,
+        }
+
+        self.privilege = prev;
+        self.sp = match prev {
+            PrivilegeLevel::Kernel => self.kernel_sp,
+            PrivilegeLevel::Supervisor => self.supervisor_sp,
+            PrivilegeLevel::User => self.user_sp,
+        };
+        self.sr = self.saved_sr & !Self::SR_PP_MASK;
+        self.pc = self.saved_pc;
     }
 
     /// Check if in kernel mode
@@ -154,6 +231,12 @@ impl RegisterFile {
         self.privilege = PrivilegeLevel::Kernel;
         self.saved_pc = 0;
         self.saved_sr = 0;
+        self.cause = 0;
+        self.tval = 0;
+        self.kernel_tvec = 0x0010;
+        self.supervisor_tvec = 0;
+        self.trap_deleg = 0;
+        self.ptbr = 0;
     }
 
     /// Get status flags
@@ -3,6 +3,10 @@
 // System call handler for CVERE VM
 // ============================================================================
 
+use std::collections::VecDeque;
+
+use crate::registers::RegisterFile;
+
 /// System call numbers
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u16)]
@@ -19,6 +23,14 @@ pub enum Syscall {
     CloseFile = 0x09,      // Close file
     ReadFile = 0x0A,       // Read from file
     WriteFile = 0x0B,      // Write to file
+
+    // Concurrency primitives
+    NewThread = 0x10,      // Spawn a thread with its own PC/SP
+    Yield = 0x11,          // Voluntarily yield to the next ready thread
+    Join = 0x12,           // Wait for a thread to exit
+    SemP = 0x13,           // Semaphore wait (P / down)
+    SemV = 0x14,           // Semaphore signal (V / up)
+
     Unknown = 0xFFFF,
 }
 
@@ -37,6 +49,11 @@ impl Syscall {
             0x09 => Syscall::CloseFile,
             0x0A => Syscall::ReadFile,
             0x0B => Syscall::WriteFile,
+            0x10 => Syscall::NewThread,
+            0x11 => Syscall::Yield,
+            0x12 => Syscall::Join,
+            0x13 => Syscall::SemP,
+            0x14 => Syscall::SemV,
             _ => Syscall::Unknown,
         }
     }
@@ -90,4 +107,123 @@ impl Console {
     pub fn clear_output(&mut self) {
         self.output.clear();
     }
+}
+
+/// A saved thread context: its register snapshot plus liveness.
+pub struct Thread {
+    pub regs: RegisterFile,
+    pub done: bool,
+}
+
+/// A counting semaphore: a signed count and a queue of blocked thread ids.
+struct Semaphore {
+    count: i32,
+    waiters: VecDeque<usize>,
+}
+
+/// Cooperative round-robin scheduler driving the concurrency syscalls.
+///
+/// Thread ids index `threads` directly. The currently running thread's live
+/// registers are owned by the VM; the scheduler saves them into the thread
+/// table on every context switch and loads the next ready context back out.
+pub struct Scheduler {
+    threads: Vec<Thread>,
+    ready: VecDeque<usize>,
+    current: usize,
+    semaphores: Vec<Semaphore>,
+}
+
+impl Scheduler {
+    /// Create a scheduler whose thread 0 owns the initial `regs` context.
+    pub fn new(regs: RegisterFile) -> Self {
+        Scheduler {
+            threads: vec![Thread { regs, done: false }],
+            ready: VecDeque::new(),
+            current: 0,
+            semaphores: Vec::new(),
+        }
+    }
+
+    /// Spawn a thread starting at `pc` with stack pointer `sp`, snapshotting
+    /// the caller's registers. Returns the new thread id.
+    pub fn new_thread(&mut self, live: &RegisterFile, pc: u16, sp: u16) -> usize {
+        let mut regs = live.clone();
+        regs.pc = pc;
+        regs.sp = sp;
+        let id = self.threads.len();
+        self.threads.push(Thread { regs, done: false });
+        self.ready.push_back(id);
+        id
+    }
+
+    /// Voluntarily switch to the next ready thread, re-queuing the caller.
+    pub fn yield_now(&mut self, live: &mut RegisterFile) {
+        let cur = self.current;
+        self.ready.push_back(cur);
+        self.switch_to_next(live);
+    }
+
+    /// Mark the current thread finished and switch away from it for good.
+    pub fn exit(&mut self, live: &mut RegisterFile) {
+        self.threads[self.current].done = true;
+        self.switch_to_next(live);
+    }
+
+    /// Join thread `tid`: returns at once if `tid` has exited or does not
+    /// exist; otherwise yields the current thread once and returns. It does
+    /// not wait for `tid` to finish.
+    pub fn join(&mut self, tid: usize, live: &mut RegisterFile) {
+        if self.threads.get(tid).map_or(true, |t| t.done) {
+            return;
+        }
+        self.yield_now(live);
+    }
+
+    /// Allocate a semaphore with initial `count`; returns its handle.
+    pub fn new_semaphore(&mut self, count: i32) -> usize {
+        let id = self.semaphores.len();
+        self.semaphores.push(Semaphore {
+            count,
+            waiters: VecDeque::new(),
+        });
+        id
+    }
+
+    /// Semaphore wait: decrement and, if the count goes negative, block the
+    /// current thread and switch to the next ready one.
+    pub fn sem_p(&mut self, sem: usize, live: &mut RegisterFile) {
+        self.semaphores[sem].count -= 1;
+        if self.semaphores[sem].count < 0 {
+            let cur = self.current;
+            self.semaphores[sem].waiters.push_back(cur);
+            self.switch_to_next(live);
+        }
+    }
+
+    /// Semaphore signal: increment and, if the count was non-positive, wake one
+    /// blocked thread by returning it to the ready queue.
+    pub fn sem_v(&mut self, sem: usize) {
+        let was_non_positive = self.semaphores[sem].count <= 0;
+        self.semaphores[sem].count += 1;
+        if was_non_positive {
+            if let Some(tid) = self.semaphores[sem].waiters.pop_front() {
+                self.ready.push_back(tid);
+            }
+        }
+    }
+
+    /// Save `live` into the current thread and load the next ready context.
+    /// If nothing is ready, the current thread keeps running.
+    fn switch_to_next(&mut self, live: &mut RegisterFile) {
+        self.threads[self.current].regs = live.clone();
+        if let Some(next) = self.ready.pop_front() {
+            self.current = next;
+            *live = self.threads[next].regs.clone();
+        }
+    }
+
+    /// True once every thread has exited.
+    pub fn all_done(&self) -> bool {
+        self.threads.iter().all(|t| t.done)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,353 @@
+// ============================================================================
+// desktop/rust/src/asm.rs
+// Two-pass assembler and disassembler for the CVERE 16-bit encoding
+// ============================================================================
+
+use std::fmt;
+
+use crate::decoder::InstructionDecoder;
+
+/// Reasons assembly can fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AsmError {
+    UnknownMnemonic(String),
+    UnknownRegister(String),
+    /// A special register named where only R0-RF can be encoded.
+    UnsupportedRegister(String),
+    BadImmediate(String),
+    OperandCount { mnemonic: String, expected: usize, found: usize },
+    UndefinedLabel(String),
+    BranchOutOfRange(String),
+    /// A BNE whose encoding would alias a reserved opcode (a trap gate or HALT).
+    BranchEncodingConflict(String),
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AsmError::UnknownMnemonic(m) => write!(f, "unknown mnemonic '{}'", m),
+            AsmError::UnknownRegister(r) => write!(f, "unknown register '{}'", r),
+            AsmError::UnsupportedRegister(r) =>
+                write!(f, "register '{}' cannot be encoded as an operand", r),
+            AsmError::BadImmediate(i) => write!(f, "bad immediate '{}'", i),
+            AsmError::OperandCount { mnemonic, expected, found } =>
+                write!(f, "{} expects {} operands, found {}", mnemonic, expected, found),
+            AsmError::UndefinedLabel(l) => write!(f, "undefined label '{}'", l),
+            AsmError::BranchOutOfRange(l) => write!(f, "branch to '{}' out of range", l),
+            AsmError::BranchEncodingConflict(m) =>
+                write!(f, "branch encoding conflicts with a reserved opcode: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// Assemble CVERE assembly `src` into a vector of 16-bit words.
+///
+/// Supports NOP, ADD, ADDI, SUB, AND, OR, XOR, NOT, SHL, SHR, LOAD, STORE,
+/// LOADI, JMP, BEQ, BNE, HALT and RETI; the extended ALU ops (MUL through SHRS)
+/// and the SYS trap gates are not assembled. Register operands are `R0`-`RF`;
+/// `PC`/`SP`/`LR`/`SR` are recognized but rejected with `UnsupportedRegister`,
+/// since operand fields encode only R0-RF. Also accepts hex (`0x..`) and
+/// decimal immediates, `;`/`//` comments, and symbolic labels. A first pass
+/// records each label's word address; the
+/// second encodes instructions, resolving branch labels to the signed word
+/// offsets `BEQ`/`BNE` expect and `JMP` labels to a 12-bit absolute target.
+pub fn assemble(src: &str) -> Result<Vec<u16>, AsmError> {
+    let lines: Vec<Vec<String>> = src.lines().map(tokenize).collect();
+
+    // Pass 1: assign each instruction an address and record label positions.
+    let mut labels = std::collections::HashMap::new();
+    let mut index: u16 = 0;
+    let mut instructions: Vec<Vec<String>> = Vec::new();
+    for tokens in lines {
+        let mut tokens = tokens;
+        // Leading `label:` tokens bind to the current address.
+        while let Some(first) = tokens.first() {
+            if let Some(label) = first.strip_suffix(':') {
+                labels.insert(label.to_string(), index * 2);
+                tokens.remove(0);
+            } else {
+                break;
+            }
+        }
+        if tokens.is_empty() {
+            continue;
+        }
+        index += 1;
+        instructions.push(tokens);
+    }
+
+    // Pass 2: encode, resolving label references.
+    let mut out = Vec::with_capacity(instructions.len());
+    for (i, tokens) in instructions.iter().enumerate() {
+        let addr = (i as u16) * 2;
+        out.push(encode(tokens, addr, &labels)?);
+    }
+    Ok(out)
+}
+
+/// Split a source line into tokens, stripping comments and commas.
+fn tokenize(line: &str) -> Vec<String> {
+    let code = line
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .split("//")
+        .next()
+        .unwrap_or("");
+    code.replace(',', " ")
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn encode(
+    tokens: &[String],
+    addr: u16,
+    labels: &std::collections::HashMap<String, u16>,
+) -> Result<u16, AsmError> {
+    let mnemonic = tokens[0].to_uppercase();
+    let ops = &tokens[1..];
+
+    let word = match mnemonic.as_str() {
+        "NOP" => 0x0000,
+        "HALT" => 0xFFFF,
+        // RETI: no operands, encoded in the 0x0 extended space (selector 0xA)
+        // to stay clear of the BNE register range.
+        "RETI" => {
+            expect(&mnemonic, ops, 0)?;
+            0x0A00
+        }
+        // R-type: OP rd, rs, rt
+        "ADD" | "SUB" | "AND" | "OR" | "XOR" | "SHL" | "SHR" => {
+            let opcode = r_opcode(&mnemonic);
+            let (rd, rs, rt) = three_regs(&mnemonic, ops)?;
+            (opcode << 12) | (rd << 8) | (rs << 4) | rt
+        }
+        // NOT rd, rs
+        "NOT" => {
+            let (rd, rs) = two_regs(&mnemonic, ops)?;
+            (0x7 << 12) | (rd << 8) | (rs << 4)
+        }
+        // I-type: OP rd, imm8
+        "ADDI" | "LOADI" => {
+            let opcode = if mnemonic == "ADDI" { 0x2 } else { 0xC };
+            expect(&mnemonic, ops, 2)?;
+            let rd = reg(&ops[0])?;
+            let imm = imm8(&ops[1])?;
+            (opcode << 12) | (rd << 8) | imm
+        }
+        // M-type: OP rd, rs, offset4
+        "LOAD" | "STORE" => {
+            let opcode = if mnemonic == "LOAD" { 0xA } else { 0xB };
+            expect(&mnemonic, ops, 3)?;
+            let rd = reg(&ops[0])?;
+            let rs = reg(&ops[1])?;
+            let off = parse_imm(&ops[2])? as u16 & 0xF;
+            (opcode << 12) | (rd << 8) | (rs << 4) | off
+        }
+        // J-type: JMP target (12-bit absolute)
+        "JMP" => {
+            expect(&mnemonic, ops, 1)?;
+            let target = resolve_absolute(&ops[0], labels)?;
+            (0xD << 12) | (target & 0xFFF)
+        }
+        // B-type: OP rc, target (signed word offset)
+        "BEQ" | "BNE" => {
+            expect(&mnemonic, ops, 2)?;
+            let rc = reg(&ops[0])?;
+            let off = resolve_branch(&ops[1], addr, labels)?;
+            let opcode = if mnemonic == "BEQ" { 0xE } else { 0xF };
+            let word = (opcode << 12) | (rc << 8) | (off as u8 as u16);
+            // BNE shares the 0xF opcode with reserved encodings: R0-R3 land on
+            // the trap gates 0xF0-0xF3, and BNE RF,-1 is 0xFFFF (HALT). Reject
+            // those rather than silently emit a miscompiled branch.
+            if mnemonic == "BNE" {
+                if rc <= 0x3 {
+                    return Err(AsmError::BranchEncodingConflict(format!(
+                        "BNE R{:X} aliases trap gate 0x{:02X}xx", rc, 0xF0 | rc
+                    )));
+                }
+                if word == 0xFFFF {
+                    return Err(AsmError::BranchEncodingConflict(
+                        "BNE RF, -1 encodes HALT (0xFFFF)".to_string(),
+                    ));
+                }
+            }
+            word
+        }
+        other => return Err(AsmError::UnknownMnemonic(other.to_string())),
+    };
+    Ok(word)
+}
+
+fn r_opcode(mnemonic: &str) -> u16 {
+    match mnemonic {
+        "ADD" => 0x1,
+        "SUB" => 0x3,
+        "AND" => 0x4,
+        "OR" => 0x5,
+        "XOR" => 0x6,
+        "SHL" => 0x8,
+        "SHR" => 0x9,
+        _ => unreachable!(),
+    }
+}
+
+fn expect(mnemonic: &str, ops: &[String], n: usize) -> Result<(), AsmError> {
+    if ops.len() != n {
+        return Err(AsmError::OperandCount {
+            mnemonic: mnemonic.to_string(),
+            expected: n,
+            found: ops.len(),
+        });
+    }
+    Ok(())
+}
+
+fn three_regs(mnemonic: &str, ops: &[String]) -> Result<(u16, u16, u16), AsmError> {
+    expect(mnemonic, ops, 3)?;
+    Ok((reg(&ops[0])?, reg(&ops[1])?, reg(&ops[2])?))
+}
+
+fn two_regs(mnemonic: &str, ops: &[String]) -> Result<(u16, u16), AsmError> {
+    expect(mnemonic, ops, 2)?;
+    Ok((reg(&ops[0])?, reg(&ops[1])?))
+}
+
+/// Parse a general-purpose register operand (`R0`-`RF`) to its index.
+fn reg(name: &str) -> Result<u16, AsmError> {
+    let upper = name.to_uppercase();
+    match upper.as_str() {
+        "PC" | "SP" | "LR" | "SR" => Err(AsmError::UnsupportedRegister(upper)),
+        _ => {
+            if let Some(rest) = upper.strip_prefix('R') {
+                if let Ok(n) = u16::from_str_radix(rest, 16) {
+                    if n < 16 {
+                        return Ok(n);
+                    }
+                }
+            }
+            Err(AsmError::UnknownRegister(name.to_string()))
+        }
+    }
+}
+
+/// Parse a hex (`0x..`) or decimal (optionally negative) immediate.
+fn parse_imm(text: &str) -> Result<i32, AsmError> {
+    let value = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        i32::from_str_radix(hex, 16)
+    } else if let Some(hex) = text.strip_prefix("-0x").or_else(|| text.strip_prefix("-0X")) {
+        i32::from_str_radix(hex, 16).map(|v| -v)
+    } else {
+        text.parse::<i32>()
+    };
+    value.map_err(|_| AsmError::BadImmediate(text.to_string()))
+}
+
+fn imm8(text: &str) -> Result<u16, AsmError> {
+    Ok((parse_imm(text)? as u16) & 0xFF)
+}
+
+/// Resolve a `JMP` target: a label's byte address or a literal.
+fn resolve_absolute(
+    text: &str,
+    labels: &std::collections::HashMap<String, u16>,
+) -> Result<u16, AsmError> {
+    if let Some(&addr) = labels.get(text) {
+        return Ok(addr);
+    }
+    if is_numeric(text) {
+        return Ok(parse_imm(text)? as u16);
+    }
+    Err(AsmError::UndefinedLabel(text.to_string()))
+}
+
+/// Resolve a branch target into the signed word offset the VM applies after
+/// advancing past the branch (target = branch_addr + 2 + offset * 2).
+fn resolve_branch(
+    text: &str,
+    addr: u16,
+    labels: &std::collections::HashMap<String, u16>,
+) -> Result<i16, AsmError> {
+    if is_numeric(text) {
+        return Ok(parse_imm(text)? as i16);
+    }
+    let target = *labels
+        .get(text)
+        .ok_or_else(|| AsmError::UndefinedLabel(text.to_string()))?;
+    let delta = (target as i32) - (addr as i32 + 2);
+    let words = delta / 2;
+    if words < i8::MIN as i32 || words > i8::MAX as i32 {
+        return Err(AsmError::BranchOutOfRange(text.to_string()));
+    }
+    Ok(words as i16)
+}
+
+fn is_numeric(text: &str) -> bool {
+    let t = text.strip_prefix('-').unwrap_or(text);
+    t.starts_with("0x") || t.starts_with("0X") || t.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Disassemble a single 16-bit word into text, for the state dump and debugger.
+pub fn disassemble(word: u16) -> String {
+    match InstructionDecoder::decode(word) {
+        Ok(decoded) => InstructionDecoder::format_instruction(&decoded),
+        Err(_) => format!(".word 0x{:04X}", word),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble_simple() {
+        let src = "
+            LOADI R1, 0x05  ; load 5
+            LOADI R2, 0x03
+            ADD R3, R1, R2
+            HALT
+        ";
+        let code = assemble(src).unwrap();
+        assert_eq!(code, vec![0xC105, 0xC203, 0x1312, 0xFFFF]);
+    }
+
+    #[test]
+    fn test_assemble_labels() {
+        // BNE must reach back to `loop`; uses R4 since the VM treats 0xF0-0xF3
+        // as traps rather than branches.
+        let src = "
+            loop:
+                ADDI R4, 0x01
+                BNE R4, loop
+                HALT
+        ";
+        let code = assemble(src).unwrap();
+        // BNE back one instruction: target - (addr + 2) = 0 - (2 + 2) = -4 => -2 words.
+        assert_eq!(code[1], 0xF4FE);
+    }
+
+    #[test]
+    fn test_disassemble_round_trip() {
+        assert_eq!(disassemble(0x1312), "ADD R3, R1, R2");
+    }
+
+    #[test]
+    fn test_bne_rejects_trap_gate_register() {
+        // BNE R3 would assemble to the trap gate 0xF3xx.
+        assert!(matches!(
+            assemble("BNE R3, 0"),
+            Err(AsmError::BranchEncodingConflict(_))
+        ));
+        // R13 is fine now that RETI no longer lives at 0xFDxx.
+        assert_eq!(assemble("BNE RD, 0").unwrap(), vec![0xFD00]);
+    }
+
+    #[test]
+    fn test_reti_round_trip() {
+        assert_eq!(assemble("RETI").unwrap(), vec![0x0A00]);
+        assert_eq!(disassemble(0x0A00), "RETI");
+    }
+}
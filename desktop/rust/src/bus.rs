@@ -0,0 +1,160 @@
+// ============================================================================
+// desktop/rust/src/bus.rs
+// Memory bus abstraction for the CVERE VM
+// ============================================================================
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::vm::VmError;
+
+/// A word-addressed memory bus.
+///
+/// Following the emulator-hal `BusAccess` pattern, every access takes an
+/// address and returns a `Result` so misaligned / out-of-bounds accesses and
+/// device errors surface as `VmError`. Factoring memory behind this trait lets
+/// the VM host memory-mapped peripherals without touching the instruction set.
+pub trait Bus {
+    fn read_u16(&mut self, addr: u16) -> Result<u16, VmError>;
+    fn write_u16(&mut self, addr: u16, val: u16) -> Result<(), VmError>;
+}
+
+/// Flat RAM backing, wrapping the VM's byte vector (little-endian words).
+pub struct RamBus {
+    ram: Vec<u8>,
+}
+
+impl RamBus {
+    /// Create `size` bytes of zeroed RAM (64KB for the default address space).
+    pub fn new(size: usize) -> Self {
+        RamBus { ram: vec![0; size] }
+    }
+}
+
+impl Bus for RamBus {
+    fn read_u16(&mut self, addr: u16) -> Result<u16, VmError> {
+        if addr & 1 != 0 {
+            return Err(VmError::MemoryAlignment { addr });
+        }
+        let i = addr as usize;
+        if i + 1 >= self.ram.len() {
+            return Err(VmError::MemoryOutOfBounds { addr });
+        }
+        Ok((self.ram[i] as u16) | ((self.ram[i + 1] as u16) << 8))
+    }
+
+    fn write_u16(&mut self, addr: u16, val: u16) -> Result<(), VmError> {
+        if addr & 1 != 0 {
+            return Err(VmError::MemoryAlignment { addr });
+        }
+        let i = addr as usize;
+        if i + 1 >= self.ram.len() {
+            return Err(VmError::MemoryOutOfBounds { addr });
+        }
+        self.ram[i] = (val & 0xFF) as u8;
+        self.ram[i + 1] = (val >> 8) as u8;
+        Ok(())
+    }
+}
+
+/// A bus that dispatches address ranges to registered devices, falling back to
+/// a backing bus (normally `RamBus`) for unmapped addresses.
+pub struct MappedBus {
+    backing: Box<dyn Bus>,
+    // (inclusive start, inclusive end, device)
+    devices: Vec<(u16, u16, Box<dyn Bus>)>,
+}
+
+impl MappedBus {
+    pub fn new(backing: Box<dyn Bus>) -> Self {
+        MappedBus {
+            backing,
+            devices: Vec::new(),
+        }
+    }
+
+    /// Map `device` over the inclusive address range `start..=end`.
+    pub fn map(&mut self, start: u16, end: u16, device: Box<dyn Bus>) {
+        self.devices.push((start, end, device));
+    }
+
+    fn device_for(&mut self, addr: u16) -> Option<&mut Box<dyn Bus>> {
+        self.devices
+            .iter_mut()
+            .find(|(start, end, _)| addr >= *start && addr <= *end)
+            .map(|(_, _, device)| device)
+    }
+}
+
+impl Bus for MappedBus {
+    fn read_u16(&mut self, addr: u16) -> Result<u16, VmError> {
+        match self.device_for(addr) {
+            Some(device) => device.read_u16(addr),
+            None => self.backing.read_u16(addr),
+        }
+    }
+
+    fn write_u16(&mut self, addr: u16, val: u16) -> Result<(), VmError> {
+        match self.device_for(addr) {
+            Some(device) => device.write_u16(addr, val),
+            None => self.backing.write_u16(addr, val),
+        }
+    }
+}
+
+/// A memory-mapped console: writing a word emits its low byte as a character.
+///
+/// By default output goes to stdout; `with_sink` instead appends to a shared
+/// buffer so the host (or a test) can observe what the program printed.
+pub struct ConsoleDevice {
+    sink: Option<Rc<RefCell<Vec<u8>>>>,
+}
+
+impl ConsoleDevice {
+    pub fn new() -> Self {
+        ConsoleDevice { sink: None }
+    }
+
+    /// Build a console that appends written bytes to `sink` instead of stdout.
+    pub fn with_sink(sink: Rc<RefCell<Vec<u8>>>) -> Self {
+        ConsoleDevice { sink: Some(sink) }
+    }
+}
+
+impl Bus for ConsoleDevice {
+    fn read_u16(&mut self, _addr: u16) -> Result<u16, VmError> {
+        Ok(0)
+    }
+
+    fn write_u16(&mut self, _addr: u16, val: u16) -> Result<(), VmError> {
+        let byte = (val & 0xFF) as u8;
+        match &self.sink {
+            Some(sink) => sink.borrow_mut().push(byte),
+            None => print!("{}", byte as char),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ram_round_trip() {
+        let mut bus = RamBus::new(65536);
+        bus.write_u16(0x10, 0xBEEF).unwrap();
+        assert_eq!(bus.read_u16(0x10).unwrap(), 0xBEEF);
+    }
+
+    #[test]
+    fn test_mapped_dispatch() {
+        let mut bus = MappedBus::new(Box::new(RamBus::new(65536)));
+        bus.map(0xFF00, 0xFF01, Box::new(ConsoleDevice::new()));
+        // Unmapped address falls through to backing RAM.
+        bus.write_u16(0x20, 0x1234).unwrap();
+        assert_eq!(bus.read_u16(0x20).unwrap(), 0x1234);
+        // Mapped console address is accepted by the device.
+        bus.write_u16(0xFF00, 'A' as u16).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+// ============================================================================
+// desktop/rust/src/debugger.rs
+// Debugging API: breakpoints, watchpoints, and single-step tracing
+// ============================================================================
+
+use std::collections::{HashMap, HashSet};
+
+/// What kind of data access a watchpoint fires on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WatchKind {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl WatchKind {
+    fn matches(&self, is_write: bool) -> bool {
+        match self {
+            WatchKind::Read => !is_write,
+            WatchKind::Write => is_write,
+            WatchKind::ReadWrite => true,
+        }
+    }
+}
+
+/// First-class debugging state owned by the VM.
+///
+/// Ported from moa's `Debuggable` concept: PC breakpoints are consulted by the
+/// fetch stage and memory watchpoints by the data-access path, so a front-end
+/// can stop, inspect, and resume the machine.
+pub struct Debugger {
+    breakpoints: HashSet<u16>,
+    watchpoints: HashMap<u16, WatchKind>,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Debugger {
+            breakpoints: HashSet::new(),
+            watchpoints: HashMap::new(),
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, addr: u16) {
+        self.breakpoints.insert(addr);
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: u16) {
+        self.breakpoints.remove(&addr);
+    }
+
+    pub fn add_watchpoint(&mut self, addr: u16, kind: WatchKind) {
+        self.watchpoints.insert(addr, kind);
+    }
+
+    pub fn remove_watchpoint(&mut self, addr: u16) {
+        self.watchpoints.remove(&addr);
+    }
+
+    /// True when `addr` holds a PC breakpoint.
+    pub fn has_breakpoint(&self, addr: u16) -> bool {
+        self.breakpoints.contains(&addr)
+    }
+
+    /// True when a watchpoint at `addr` fires for the given access direction.
+    pub fn watch_triggered(&self, addr: u16, is_write: bool) -> bool {
+        self.watchpoints
+            .get(&addr)
+            .map_or(false, |kind| kind.matches(is_write))
+    }
+}
+
+/// A structured record of a single executed cycle, for front-end stepping.
+#[derive(Debug, Clone)]
+pub struct TraceRecord {
+    pub pc: u16,
+    pub instruction: u16,
+    pub mnemonic: &'static str,
+    pub registers_before: [u16; 16],
+    pub registers_after: [u16; 16],
+    pub flags_before: u16,
+    pub flags_after: u16,
+}
@@ -3,6 +3,63 @@
 // Instruction decoder module for CVERE VM
 // ============================================================================
 
+use std::fmt;
+use std::sync::OnceLock;
+
+/// A trap or fault condition, with its trap cause code and trap value.
+///
+/// Each variant carries enough context (the offending word or address) to be
+/// logged and to populate the trap `cause`/`tval` registers.
+/// `InstructionDecoder::decode` returns `IllegalInstruction` for illegal
+/// encodings; the VM's execute path reports its errors as `VmError`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    IllegalInstruction(u16),
+    PrivilegeViolation,
+    DivideByZero,
+    MisalignedAccess(u16),
+    PageFault(u16),
+    Breakpoint,
+}
+
+impl Fault {
+    /// Cause code written to the trap `cause` register on delivery.
+    pub fn cause_code(&self) -> u16 {
+        match self {
+            Fault::DivideByZero => 0x0,
+            Fault::PrivilegeViolation => 0x1,
+            Fault::IllegalInstruction(_) => 0x2,
+            Fault::Breakpoint => 0x3,
+            Fault::MisalignedAccess(_) => 0x4,
+            Fault::PageFault(_) => 0xC,
+        }
+    }
+
+    /// Faulting value or address written to the trap `tval` register.
+    pub fn trap_value(&self) -> u16 {
+        match self {
+            Fault::IllegalInstruction(word) => *word,
+            Fault::MisalignedAccess(addr) | Fault::PageFault(addr) => *addr,
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Fault::IllegalInstruction(word) => write!(f, "illegal instruction 0x{:04X}", word),
+            Fault::PrivilegeViolation => write!(f, "privilege violation"),
+            Fault::DivideByZero => write!(f, "divide by zero"),
+            Fault::MisalignedAccess(addr) => write!(f, "misaligned access at 0x{:04X}", addr),
+            Fault::PageFault(addr) => write!(f, "page fault at 0x{:04X}", addr),
+            Fault::Breakpoint => write!(f, "breakpoint"),
+        }
+    }
+}
+
+impl std::error::Error for Fault {}
+
 /// Instruction format types
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InstructionFormat {
@@ -31,9 +88,29 @@ pub struct DecodedInstruction {
 
 pub struct InstructionDecoder;
 
+/// Precomputed `(format, mnemonic)` for every 16-bit encoding.
+///
+/// The opcode space is sparse (16 primary opcodes plus a handful of
+/// `0xF`-prefixed extended ops), so rather than re-run `classify_instruction`
+/// on every fetch we fill a flat table once on first use by iterating all
+/// encodings through the existing classification logic. The hot decode path
+/// then reduces to a single array index plus field extraction.
+static DECODE_TABLE: OnceLock<Vec<(InstructionFormat, &'static str)>> = OnceLock::new();
+
+fn decode_table() -> &'static [(InstructionFormat, &'static str)] {
+    DECODE_TABLE.get_or_init(|| {
+        (0..=u16::MAX)
+            .map(|word| {
+                let opcode = ((word >> 12) & 0xF) as u8;
+                InstructionDecoder::classify_instruction(word, opcode)
+            })
+            .collect()
+    })
+}
+
 impl InstructionDecoder {
-    /// Decode a 16-bit instruction
-    pub fn decode(instruction: u16) -> DecodedInstruction {
+    /// Decode a 16-bit instruction, returning a `Fault` for illegal encodings.
+    pub fn decode(instruction: u16) -> Result<DecodedInstruction, Fault> {
         let opcode = ((instruction >> 12) & 0xF) as u8;
         let rd = ((instruction >> 8) & 0xF) as u8;
         let rs = ((instruction >> 4) & 0xF) as u8;
@@ -42,10 +119,16 @@ impl InstructionDecoder {
         let offset = (instruction & 0xF) as u8;
         let addr12 = instruction & 0xFFF;
 
-        // Determine format and mnemonic
-        let (format, mnemonic) = Self::classify_instruction(instruction, opcode);
+        // Look up the precomputed classification for this encoding.
+        let (format, mnemonic) = decode_table()[instruction as usize];
 
-        DecodedInstruction {
+        // An unclassifiable encoding is an illegal instruction, not a silent
+        // "UNKNOWN" mnemonic.
+        if mnemonic == "UNKNOWN" {
+            return Err(Fault::IllegalInstruction(instruction));
+        }
+
+        Ok(DecodedInstruction {
             format,
             opcode,
             rd,
@@ -55,7 +138,7 @@ impl InstructionDecoder {
             offset,
             addr12,
             mnemonic,
-        }
+        })
     }
 
     fn classify_instruction(instruction: u16, opcode: u8) -> (InstructionFormat, &'static str) {
@@ -71,17 +154,37 @@ impl InstructionDecoder {
         if opcode == 0xF {
             let extended_op = ((instruction >> 8) & 0xFF) as u8;
             match extended_op {
-                0xF0 => return (InstructionFormat::Extended, "CALL"),
-                0xF1 => return (InstructionFormat::Extended, "RET"),
-                0xF2 => return (InstructionFormat::Extended, "PUSH"),
-                0xF3 => return (InstructionFormat::Extended, "POP"),
+                // Reserved gates 0xF0-0xF3 are syscall traps whose low byte
+                // selects a handler (see the VM's exec_syscall); they are not
+                // branches, which is why BNE R0-R3 is unencodable.
+                0xF0..=0xF3 => return (InstructionFormat::Extended, "SYS"),
                 _ => return (InstructionFormat::BType, "BNE"),
             }
         }
 
         // Standard instruction decoding
         match opcode {
-            0x0 => (InstructionFormat::Special, "NOP"),
+            // The 0x0 opcode is the NOP space; its second nibble selects the
+            // extended ALU ops, which take their operands from the low byte
+            // (destination implied in Rs, CMP writes none). Encoding them here
+            // keeps the 0xF range, apart from the 0xF0-0xF3 trap gates, free
+            // for BNE's register field.
+            0x0 => match (instruction >> 8) & 0xF {
+                0x1 => (InstructionFormat::Extended, "MUL"),
+                0x2 => (InstructionFormat::Extended, "MULU"),
+                0x3 => (InstructionFormat::Extended, "DIV"),
+                0x4 => (InstructionFormat::Extended, "DIVU"),
+                0x5 => (InstructionFormat::Extended, "MOD"),
+                0x6 => (InstructionFormat::Extended, "MODU"),
+                0x7 => (InstructionFormat::Extended, "CMP"),
+                0x8 => (InstructionFormat::Extended, "CMPU"),
+                0x9 => (InstructionFormat::Extended, "SHRS"),
+                0xA => (InstructionFormat::Special, "RETI"),
+                // Selector 0x0 is NOP; the remaining selectors have no defined
+                // op, so report them as illegal to match the VM's InvalidOpcode.
+                0x0 => (InstructionFormat::Special, "NOP"),
+                _ => (InstructionFormat::Special, "UNKNOWN"),
+            },
             0x1 => (InstructionFormat::RType, "ADD"),
             0x2 => (InstructionFormat::IType, "ADDI"),
             0x3 => (InstructionFormat::RType, "SUB"),
@@ -132,7 +235,16 @@ impl InstructionDecoder {
                 format!("{} R{:X}, {}", decoded.mnemonic, decoded.rd, offset)
             }
             InstructionFormat::Extended => {
-                format!("{}", decoded.mnemonic)
+                match decoded.mnemonic {
+                    // Extended ALU ops disassemble with their register operands.
+                    "MUL" | "MULU" | "DIV" | "DIVU" | "MOD" | "MODU" | "CMP" | "CMPU"
+                    | "SHRS" => {
+                        format!("{} R{:X}, R{:X}", decoded.mnemonic, decoded.rs, decoded.rt)
+                    }
+                    // Syscall trap gate: the low byte is the syscall number.
+                    "SYS" => format!("SYS 0x{:02X}", decoded.imm8),
+                    _ => format!("{}", decoded.mnemonic),
+                }
             }
             InstructionFormat::Special => {
                 format!("{}", decoded.mnemonic)
@@ -142,9 +254,12 @@ impl InstructionDecoder {
 
     /// Disassemble instruction with address
     pub fn disassemble(address: u16, instruction: u16) -> String {
-        let decoded = Self::decode(instruction);
-        format!("{:04X}: {:04X}  {}", 
-            address, instruction, Self::format_instruction(&decoded))
+        match Self::decode(instruction) {
+            Ok(decoded) => format!("{:04X}: {:04X}  {}",
+                address, instruction, Self::format_instruction(&decoded)),
+            Err(_) => format!("{:04X}: {:04X}  .word 0x{:04X}",
+                address, instruction, instruction),
+        }
     }
 }
 
@@ -155,7 +270,7 @@ mod tests {
     #[test]
     fn test_decode_add() {
         let instr = 0x1312; // ADD R3, R1, R2
-        let decoded = InstructionDecoder::decode(instr);
+        let decoded = InstructionDecoder::decode(instr).unwrap();
         assert_eq!(decoded.format, InstructionFormat::RType);
         assert_eq!(decoded.mnemonic, "ADD");
         assert_eq!(decoded.rd, 3);
@@ -166,17 +281,40 @@ mod tests {
     #[test]
     fn test_decode_loadi() {
         let instr = 0xC105; // LOADI R1, 0x05
-        let decoded = InstructionDecoder::decode(instr);
+        let decoded = InstructionDecoder::decode(instr).unwrap();
         assert_eq!(decoded.format, InstructionFormat::IType);
         assert_eq!(decoded.mnemonic, "LOADI");
         assert_eq!(decoded.rd, 1);
         assert_eq!(decoded.imm8, 0x05);
     }
 
+    #[test]
+    fn test_decode_mul() {
+        let instr = 0x0112; // MUL R1, R2
+        let decoded = InstructionDecoder::decode(instr).unwrap();
+        assert_eq!(decoded.format, InstructionFormat::Extended);
+        assert_eq!(decoded.mnemonic, "MUL");
+        assert_eq!(decoded.rs, 1);
+        assert_eq!(decoded.rt, 2);
+        assert_eq!(InstructionDecoder::format_instruction(&decoded), "MUL R1, R2");
+    }
+
+    #[test]
+    fn test_decode_table_matches_classifier() {
+        // The precomputed table must agree with the classifier for every word.
+        for word in 0..=u16::MAX {
+            let opcode = ((word >> 12) & 0xF) as u8;
+            assert_eq!(
+                decode_table()[word as usize],
+                InstructionDecoder::classify_instruction(word, opcode)
+            );
+        }
+    }
+
     #[test]
     fn test_decode_halt() {
         let instr = 0xFFFF; // HALT
-        let decoded = InstructionDecoder::decode(instr);
+        let decoded = InstructionDecoder::decode(instr).unwrap();
         assert_eq!(decoded.format, InstructionFormat::Special);
         assert_eq!(decoded.mnemonic, "HALT");
     }
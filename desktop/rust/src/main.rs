@@ -1,3 +1,8 @@
+mod asm;
+mod bus;
+mod debugger;
+mod decoder;
+mod trap;
 mod vm;
 
 use vm::CVEREVM;
@@ -5,7 +10,7 @@ use vm::CVEREVM;
 fn main() {
     let program = vec![
         0xC105, 0xC203, 0x1312, 0xFFFF,
-        0xC100, 0xC20A, 0x2101, 0x3321, 0xF3FD, 0xFFFF,
+        0xC100, 0xC20A, 0x2101, 0x3421, 0xF4FD, 0xFFFF,
     ]; // Machine code to run
 
     let mut vm = CVEREVM::new();
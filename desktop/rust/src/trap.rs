@@ -0,0 +1,113 @@
+// ============================================================================
+// desktop/rust/src/trap.rs
+// Syscall/trap dispatch for the reserved extended opcodes (0xF0-0xF3)
+// ============================================================================
+
+use std::collections::VecDeque;
+
+use crate::vm::VmError;
+
+/// Syscall arguments, taken from registers R1-R3 at the trap site.
+#[derive(Debug, Clone, Copy)]
+pub struct SyscallArgs {
+    pub a0: u16,
+    pub a1: u16,
+    pub a2: u16,
+}
+
+/// What the VM should do after a syscall returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyscallAction {
+    Continue,
+    Halt,
+}
+
+/// The result of a syscall: return words (written back to R1/R2) and an action.
+#[derive(Debug, Clone, Copy)]
+pub struct SyscallOutcome {
+    pub ret0: u16,
+    pub ret1: u16,
+    pub action: SyscallAction,
+}
+
+impl SyscallOutcome {
+    /// A syscall that returns `value` and keeps running.
+    pub fn value(value: u16) -> Self {
+        SyscallOutcome {
+            ret0: value,
+            ret1: 0,
+            action: SyscallAction::Continue,
+        }
+    }
+
+    /// A syscall that halts the VM.
+    pub fn halt() -> Self {
+        SyscallOutcome {
+            ret0: 0,
+            ret1: 0,
+            action: SyscallAction::Halt,
+        }
+    }
+}
+
+/// A handler for one syscall number, dispatched from the trap layer.
+///
+/// Inspired by BurritOS's exception handler: the low byte of the trap
+/// instruction selects the handler, register arguments are passed in, and the
+/// return value is written back into R1/R2.
+pub trait SyscallHandler {
+    fn handle(&mut self, args: SyscallArgs) -> Result<SyscallOutcome, VmError>;
+}
+
+/// `SC_EXIT`: halt the VM.
+pub struct ExitHandler;
+
+impl SyscallHandler for ExitHandler {
+    fn handle(&mut self, _args: SyscallArgs) -> Result<SyscallOutcome, VmError> {
+        Ok(SyscallOutcome::halt())
+    }
+}
+
+/// `SC_WRITE` (char): print the low byte of R1 as a character.
+pub struct WriteCharHandler;
+
+impl SyscallHandler for WriteCharHandler {
+    fn handle(&mut self, args: SyscallArgs) -> Result<SyscallOutcome, VmError> {
+        print!("{}", (args.a0 & 0xFF) as u8 as char);
+        Ok(SyscallOutcome::value(0))
+    }
+}
+
+/// `SC_WRITE` (word): print R1 as a hex word.
+pub struct WriteWordHandler;
+
+impl SyscallHandler for WriteWordHandler {
+    fn handle(&mut self, args: SyscallArgs) -> Result<SyscallOutcome, VmError> {
+        print!("0x{:04X}", args.a0);
+        Ok(SyscallOutcome::value(0))
+    }
+}
+
+/// `SC_READ` (word): pop the next word from a host input queue (0 when empty).
+pub struct ReadWordHandler {
+    input: VecDeque<u16>,
+}
+
+impl ReadWordHandler {
+    pub fn new() -> Self {
+        ReadWordHandler {
+            input: VecDeque::new(),
+        }
+    }
+
+    /// Queue a word to be returned by a future read.
+    pub fn queue(&mut self, word: u16) {
+        self.input.push_back(word);
+    }
+}
+
+impl SyscallHandler for ReadWordHandler {
+    fn handle(&mut self, _args: SyscallArgs) -> Result<SyscallOutcome, VmError> {
+        Ok(SyscallOutcome::value(self.input.pop_front().unwrap_or(0)))
+    }
+}
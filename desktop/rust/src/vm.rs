@@ -1,15 +1,25 @@
 // CVERE Virtual Machine - Core execution engine
 // vm.rs - Main VM executor
 
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 
+use crate::bus::{Bus, ConsoleDevice, MappedBus, RamBus};
+use crate::debugger::{Debugger, TraceRecord};
+use crate::decoder::InstructionDecoder;
+use crate::trap::{
+    ExitHandler, ReadWordHandler, SyscallAction, SyscallArgs, SyscallHandler, WriteCharHandler,
+    WriteWordHandler,
+};
+
 /// Status register flags
 #[derive(Debug, Clone, Copy)]
 pub struct StatusFlags {
-    pub zero: bool,      // Z flag
-    pub negative: bool,  // N flag
-    pub carry: bool,     // C flag
-    pub overflow: bool,  // V flag
+    pub zero: bool,             // Z flag
+    pub negative: bool,         // N flag
+    pub carry: bool,            // C flag
+    pub overflow: bool,         // V flag
+    pub interrupt_enable: bool, // I flag: interrupts accepted when set
 }
 
 impl StatusFlags {
@@ -19,6 +29,7 @@ impl StatusFlags {
             negative: false,
             carry: false,
             overflow: false,
+            interrupt_enable: true, // interrupts enabled at boot
         }
     }
 
@@ -28,6 +39,7 @@ impl StatusFlags {
         if self.negative { sr |= 1 << 1; }
         if self.carry { sr |= 1 << 2; }
         if self.overflow { sr |= 1 << 3; }
+        if self.interrupt_enable { sr |= 1 << 4; }
         sr
     }
 
@@ -37,10 +49,50 @@ impl StatusFlags {
             negative: (sr & (1 << 1)) != 0,
             carry: (sr & (1 << 2)) != 0,
             overflow: (sr & (1 << 3)) != 0,
+            interrupt_enable: (sr & (1 << 4)) != 0,
+        }
+    }
+}
+
+/// Machine-readable reasons a VM cycle can fail.
+///
+/// Modeled on moa's `ErrorType`/`EmulatorErrorKind`: callers get a typed
+/// failure instead of a formatted string, and the previously silent
+/// out-of-bounds / misaligned memory paths become observable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VmError {
+    /// Word access to an odd address (the VM is word-oriented).
+    MemoryAlignment { addr: u16 },
+    /// Access past the end of physical memory.
+    MemoryOutOfBounds { addr: u16 },
+    /// Opcode with no defined behavior.
+    InvalidOpcode { opcode: u16 },
+    /// Division or remainder with a zero divisor.
+    DivideByZero,
+    /// Execution stopped at a breakpoint.
+    Breakpoint,
+    /// The VM has halted and cannot step further.
+    Halt,
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VmError::MemoryAlignment { addr } =>
+                write!(f, "misaligned memory access at 0x{:04X}", addr),
+            VmError::MemoryOutOfBounds { addr } =>
+                write!(f, "memory access out of bounds at 0x{:04X}", addr),
+            VmError::InvalidOpcode { opcode } =>
+                write!(f, "invalid opcode: 0x{:X}", opcode),
+            VmError::DivideByZero => write!(f, "divide by zero"),
+            VmError::Breakpoint => write!(f, "breakpoint"),
+            VmError::Halt => write!(f, "VM is halted"),
         }
     }
 }
 
+impl std::error::Error for VmError {}
+
 /// CVERE Virtual Machine
 pub struct CVEREVM {
     // General purpose registers (R0-RF)
@@ -52,76 +104,118 @@ pub struct CVEREVM {
     pub lr: u16,           // Link Register
     pub sr: StatusFlags,   // Status Register
     
-    // Memory (64KB)
-    pub memory: Vec<u8>,
-    
+    // Memory, accessed through a bus so peripherals can be mapped in
+    pub bus: Box<dyn Bus>,
+
     // Execution state
     pub halted: bool,
     pub cycle_count: u64,
+
+    // Syscall/trap handlers, keyed by syscall number
+    syscalls: HashMap<u8, Box<dyn SyscallHandler>>,
+
+    // Debugging state: breakpoints and watchpoints
+    pub debugger: Debugger,
+    // Set while resuming so the instruction at a breakpoint runs once.
+    resume_over_break: bool,
+    // Set when a data watchpoint fired this cycle; reported after the
+    // instruction finishes so the access completes and the VM stays resumable.
+    watch_hit: bool,
+
+    // Pending interrupt vectors awaiting service
+    interrupts: VecDeque<u16>,
 }
 
 impl CVEREVM {
-    /// Create a new VM instance
+    /// Create a new VM instance with the default memory map: 64KB of RAM with a
+    /// console device mapped at 0xFF00 so programs can do MMIO out of the box.
     pub fn new() -> Self {
-        CVEREVM {
+        let mut mapped = MappedBus::new(Box::new(RamBus::new(65536)));
+        mapped.map(0xFF00, 0xFF01, Box::new(ConsoleDevice::new()));
+        Self::with_bus(Box::new(mapped))
+    }
+
+    /// Create a VM over a caller-supplied bus, for custom memory maps and
+    /// peripherals. The built-in syscall handlers are registered as in `new`.
+    pub fn with_bus(bus: Box<dyn Bus>) -> Self {
+        let mut vm = CVEREVM {
             registers: [0; 16],
             pc: 0,
             sp: 0xFFFE,  // Stack grows downward from top of memory
             lr: 0,
             sr: StatusFlags::new(),
-            memory: vec![0; 65536],
+            bus,
             halted: false,
             cycle_count: 0,
-        }
+            syscalls: HashMap::new(),
+            debugger: Debugger::new(),
+            resume_over_break: false,
+            watch_hit: false,
+            interrupts: VecDeque::new(),
+        };
+        // Built-in handlers so programs can do real I/O out of the box.
+        vm.register_syscall(0x00, Box::new(ExitHandler));
+        vm.register_syscall(0x01, Box::new(WriteCharHandler));
+        vm.register_syscall(0x02, Box::new(WriteWordHandler));
+        vm.register_syscall(0x03, Box::new(ReadWordHandler::new()));
+        vm
+    }
+
+    /// Register a handler for syscall `num` (the low byte of a trap opcode).
+    pub fn register_syscall(&mut self, num: u8, handler: Box<dyn SyscallHandler>) {
+        self.syscalls.insert(num, handler);
     }
 
     /// Load program into memory
     pub fn load_program(&mut self, program: &[u16], start_address: u16) {
-        let mut addr = start_address as usize;
+        let mut addr = start_address;
         for &instruction in program {
-            if addr + 1 < self.memory.len() {
-                // Little-endian storage
-                self.memory[addr] = (instruction & 0xFF) as u8;
-                self.memory[addr + 1] = (instruction >> 8) as u8;
-                addr += 2;
+            if self.bus.write_u16(addr, instruction).is_err() {
+                break;
             }
+            addr = addr.wrapping_add(2);
         }
     }
 
     /// Fetch instruction from memory
-    fn fetch(&mut self) -> u16 {
-        let addr = self.pc as usize;
-        if addr + 1 >= self.memory.len() {
-            return 0xFFFF; // HALT on out of bounds
+    fn fetch(&mut self) -> Result<u16, VmError> {
+        // Honor PC breakpoints, but let a resume step over the one we stopped on.
+        if self.debugger.has_breakpoint(self.pc) {
+            if self.resume_over_break {
+                self.resume_over_break = false;
+            } else {
+                return Err(VmError::Breakpoint);
+            }
         }
-        
-        // Little-endian fetch
-        let low = self.memory[addr] as u16;
-        let high = self.memory[addr + 1] as u16;
+        // Instruction fetches bypass data watchpoints.
+        let word = self.bus.read_u16(self.pc)?;
         self.pc = self.pc.wrapping_add(2);
-        
-        (high << 8) | low
-    }
-
-    /// Read from memory (word-aligned)
-    fn read_memory(&self, address: u16) -> u16 {
-        let addr = address as usize;
-        if addr + 1 >= self.memory.len() {
-            return 0;
+        Ok(word)
+    }
+
+    /// Read a word through the bus, honoring read watchpoints.
+    ///
+    /// The load is performed and its value returned before the watchpoint is
+    /// recorded, so the instruction completes normally; `step` reports the stop
+    /// only after the cycle finishes, leaving the machine resumable.
+    fn read_memory(&mut self, address: u16) -> Result<u16, VmError> {
+        let value = self.bus.read_u16(address)?;
+        if self.debugger.watch_triggered(address, false) {
+            self.watch_hit = true;
         }
-        
-        let low = self.memory[addr] as u16;
-        let high = self.memory[addr + 1] as u16;
-        (high << 8) | low
+        Ok(value)
     }
 
-    /// Write to memory (word-aligned)
-    fn write_memory(&mut self, address: u16, value: u16) {
-        let addr = address as usize;
-        if addr + 1 < self.memory.len() {
-            self.memory[addr] = (value & 0xFF) as u8;
-            self.memory[addr + 1] = (value >> 8) as u8;
+    /// Write a word through the bus, honoring write watchpoints.
+    ///
+    /// The store is performed before the watchpoint is recorded so the machine
+    /// stays resumable: the next run continues with the following instruction.
+    fn write_memory(&mut self, address: u16, value: u16) -> Result<(), VmError> {
+        self.bus.write_u16(address, value)?;
+        if self.debugger.watch_triggered(address, true) {
+            self.watch_hit = true;
         }
+        Ok(())
     }
 
     /// Update status flags based on result
@@ -130,28 +224,52 @@ impl CVEREVM {
         self.sr.negative = (result & 0x8000) != 0;
     }
 
-    /// Update flags with carry
-    fn update_flags_with_carry(&mut self, result: u32) {
-        let result_16 = result as u16;
-        self.sr.zero = result_16 == 0;
-        self.sr.negative = (result_16 & 0x8000) != 0;
-        self.sr.carry = result > 0xFFFF;
+    /// Compute `a + b`, setting all of NZCV, and return the 16-bit result.
+    ///
+    /// Carry is the unsigned carry-out; overflow is set when both operands
+    /// share a sign that differs from the result's sign.
+    fn update_flags_add(&mut self, a: u16, b: u16) -> u16 {
+        let wide = a as u32 + b as u32;
+        let result = wide as u16;
+        self.sr.zero = result == 0;
+        self.sr.negative = (result & 0x8000) != 0;
+        self.sr.carry = wide > 0xFFFF;
+        let (sa, sb, sr) = (a & 0x8000, b & 0x8000, result & 0x8000);
+        self.sr.overflow = sa == sb && sa != sr;
+        result
+    }
+
+    /// Compute `a - b`, setting all of NZCV, and return the 16-bit result.
+    ///
+    /// Carry is the borrow-out convention (`a >= b`); overflow is set when the
+    /// operands differ in sign and the result's sign doesn't match `a`'s.
+    fn update_flags_sub(&mut self, a: u16, b: u16) -> u16 {
+        let result = a.wrapping_sub(b);
+        self.sr.zero = result == 0;
+        self.sr.negative = (result & 0x8000) != 0;
+        self.sr.carry = a >= b;
+        let (sa, sb, sr) = (a & 0x8000, b & 0x8000, result & 0x8000);
+        self.sr.overflow = sa != sb && sr != sa;
+        result
     }
 
     /// Execute a single instruction
-    pub fn step(&mut self) -> Result<(), String> {
+    pub fn step(&mut self) -> Result<(), VmError> {
         if self.halted {
-            return Err("VM is halted".to_string());
+            return Err(VmError::Halt);
         }
 
-        let instruction = self.fetch();
-        self.cycle_count += 1;
+        // Service a pending interrupt before fetching: save PC/SR, jump to the
+        // vector, and mask further interrupts until RETI.
+        self.service_interrupt()?;
 
+        let instruction = self.fetch()?;
         // Decode opcode
         let opcode = (instruction >> 12) & 0xF;
+        self.cycle_count += Self::instruction_cost(opcode);
 
-        match opcode {
-            0x0 => self.exec_nop(),
+        let result = match opcode {
+            0x0 => self.exec_ext_alu(instruction),
             0x1 => self.exec_add(instruction),
             0x2 => self.exec_addi(instruction),
             0x3 => self.exec_sub(instruction),
@@ -167,69 +285,195 @@ impl CVEREVM {
             0xD => self.exec_jmp(instruction),
             0xE => self.exec_beq(instruction),
             0xF => self.exec_bne_or_extended(instruction),
-            _ => Err(format!("Invalid opcode: 0x{:X}", opcode)),
+            _ => Err(VmError::InvalidOpcode { opcode }),
+        };
+        result?;
+
+        // A data watchpoint fires only after its access has completed, so the
+        // stop is resumable: the next run continues with the following cycle.
+        if self.watch_hit {
+            self.watch_hit = false;
+            return Err(VmError::Breakpoint);
         }
+        Ok(())
     }
 
-    /// Run until HALT or error
-    pub fn run(&mut self, max_cycles: u64) -> Result<u64, String> {
+    /// Run until HALT or error.
+    ///
+    /// Stops and returns `Err(VmError::Breakpoint)` when a breakpoint or
+    /// watchpoint trips, leaving the VM resumable: calling `run` again steps
+    /// over the breakpoint that stopped it and continues.
+    pub fn run(&mut self, max_cycles: u64) -> Result<u64, VmError> {
         let start_cycle = self.cycle_count;
-        
+        // Resume across the breakpoint we're currently parked on, if any.
+        self.resume_over_break = true;
+
         while !self.halted && (self.cycle_count - start_cycle) < max_cycles {
             self.step()?;
+            self.resume_over_break = false;
         }
-        
+
         Ok(self.cycle_count - start_cycle)
     }
 
+    /// Execute one instruction and return a structured trace of the cycle:
+    /// the decoded instruction plus register and flag state before and after.
+    pub fn step_with_trace(&mut self) -> Result<TraceRecord, VmError> {
+        let pc = self.pc;
+        let instruction = self.bus.read_u16(pc).unwrap_or(0xFFFF);
+        let mnemonic = InstructionDecoder::decode(instruction)
+            .map(|d| d.mnemonic)
+            .unwrap_or("ILLEGAL");
+        let registers_before = self.registers;
+        let flags_before = self.sr.to_u16();
+
+        self.step()?;
+
+        Ok(TraceRecord {
+            pc,
+            instruction,
+            mnemonic,
+            registers_before,
+            registers_after: self.registers,
+            flags_before,
+            flags_after: self.sr.to_u16(),
+        })
+    }
+
     // Instruction implementations
 
-    fn exec_nop(&mut self) -> Result<(), String> {
+    /// Opcode 0x0: NOP plus the extended ALU ops selected by the second
+    /// nibble. Operands come from the low byte (`Rs`, `Rt`); the destination
+    /// is `Rs` for the arithmetic/shift ops, while CMP/CMPU only set flags.
+    /// Division by a zero divisor raises a fault rather than producing garbage.
+    fn exec_ext_alu(&mut self, instr: u16) -> Result<(), VmError> {
+        let selector = (instr >> 8) & 0xF;
+        let rs = ((instr >> 4) & 0xF) as usize;
+        let rt = (instr & 0xF) as usize;
+        let a = self.registers[rs];
+        let b = self.registers[rt];
+
+        match selector {
+            0x0 => return Ok(()), // NOP
+            0x1 => {
+                // MUL (signed): low 16 bits of the product; overflow when the
+                // full product doesn't fit back into 16 signed bits.
+                let wide = (a as i16 as i32) * (b as i16 as i32);
+                let result = wide as u16;
+                self.registers[rs] = result;
+                self.update_flags(result);
+                self.sr.overflow = wide != result as i16 as i32;
+                self.sr.carry = false;
+            }
+            0x2 => {
+                // MULU (unsigned): carry marks a truncated high half.
+                let wide = a as u32 * b as u32;
+                let result = wide as u16;
+                self.registers[rs] = result;
+                self.update_flags(result);
+                self.sr.carry = wide > 0xFFFF;
+                self.sr.overflow = false;
+            }
+            0x3 => {
+                if b == 0 {
+                    return Err(VmError::DivideByZero);
+                }
+                let result = (a as i16).wrapping_div(b as i16) as u16;
+                self.registers[rs] = result;
+                self.update_flags(result);
+            }
+            0x4 => {
+                if b == 0 {
+                    return Err(VmError::DivideByZero);
+                }
+                let result = a / b;
+                self.registers[rs] = result;
+                self.update_flags(result);
+            }
+            0x5 => {
+                if b == 0 {
+                    return Err(VmError::DivideByZero);
+                }
+                let result = (a as i16).wrapping_rem(b as i16) as u16;
+                self.registers[rs] = result;
+                self.update_flags(result);
+            }
+            0x6 => {
+                if b == 0 {
+                    return Err(VmError::DivideByZero);
+                }
+                let result = a % b;
+                self.registers[rs] = result;
+                self.update_flags(result);
+            }
+            0x7 => {
+                // CMP (signed): flags from `a - b`, no register written.
+                self.update_flags_sub(a, b);
+            }
+            0x8 => {
+                // CMPU (unsigned): carry is the `a >= b` no-borrow convention.
+                let result = a.wrapping_sub(b);
+                self.sr.zero = result == 0;
+                self.sr.negative = (result & 0x8000) != 0;
+                self.sr.carry = a >= b;
+                self.sr.overflow = false;
+            }
+            0x9 => {
+                // SHRS: arithmetic right shift, carry is the last bit out.
+                let shift = b & 0xF;
+                let result = ((a as i16) >> shift) as u16;
+                self.registers[rs] = result;
+                self.update_flags(result);
+                self.sr.carry = shift != 0 && (a >> (shift - 1)) & 1 != 0;
+            }
+            // RETI lives here rather than in the 0xF branch space so it doesn't
+            // shadow a BNE register; it takes no operands.
+            0xA => return self.exec_reti(),
+            _ => return Err(VmError::InvalidOpcode { opcode: instr }),
+        }
+
+        self.registers[0] = 0;
         Ok(())
     }
 
-    fn exec_add(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_add(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let rt = (instr & 0xF) as usize;
         
-        let result = self.registers[rs].wrapping_add(self.registers[rt]) as u32;
-        println!("{}", result as u16);
-        self.registers[rd] = result as u16;
-        
+        let result = self.update_flags_add(self.registers[rs], self.registers[rt]);
+        self.registers[rd] = result;
+
         // R0 always reads as 0
         self.registers[0] = 0;
-        
-        self.update_flags_with_carry(result);
+
         Ok(())
     }
 
-    fn exec_addi(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_addi(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let imm = (instr & 0xFF) as u16;
-        
-        let result = self.registers[rd].wrapping_add(imm) as u32;
-        self.registers[rd] = result as u16;
+
+        let result = self.update_flags_add(self.registers[rd], imm);
+        self.registers[rd] = result;
         self.registers[0] = 0;
-        
-        self.update_flags_with_carry(result);
+
         Ok(())
     }
 
-    fn exec_sub(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_sub(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let rt = (instr & 0xF) as usize;
         
-        let result = self.registers[rs].wrapping_sub(self.registers[rt]);
+        let result = self.update_flags_sub(self.registers[rs], self.registers[rt]);
         self.registers[rd] = result;
         self.registers[0] = 0;
-        
-        self.update_flags(result);
+
         Ok(())
     }
 
-    fn exec_and(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_and(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let rt = (instr & 0xF) as usize;
@@ -242,7 +486,7 @@ impl CVEREVM {
         Ok(())
     }
 
-    fn exec_or(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_or(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let rt = (instr & 0xF) as usize;
@@ -255,7 +499,7 @@ impl CVEREVM {
         Ok(())
     }
 
-    fn exec_xor(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_xor(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let rt = (instr & 0xF) as usize;
@@ -268,7 +512,7 @@ impl CVEREVM {
         Ok(())
     }
 
-    fn exec_not(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_not(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         
@@ -280,60 +524,68 @@ impl CVEREVM {
         Ok(())
     }
 
-    fn exec_shl(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_shl(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let rt = (instr & 0xF) as usize;
         
+        let value = self.registers[rs];
         let shift = self.registers[rt] & 0xF; // Limit shift to 0-15
-        let result = self.registers[rs] << shift;
+        let result = value << shift;
         self.registers[rd] = result;
         self.registers[0] = 0;
-        
+
         self.update_flags(result);
+        // Carry is the last bit shifted out of the top.
+        self.sr.carry = shift != 0 && (value >> (16 - shift)) & 1 != 0;
+        // Overflow on a sign change across the shift.
+        self.sr.overflow = (value & 0x8000) != (result & 0x8000);
         Ok(())
     }
 
-    fn exec_shr(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_shr(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let rt = (instr & 0xF) as usize;
         
+        let value = self.registers[rs];
         let shift = self.registers[rt] & 0xF;
-        let result = self.registers[rs] >> shift;
+        let result = value >> shift;
         self.registers[rd] = result;
         self.registers[0] = 0;
-        
+
         self.update_flags(result);
+        // Carry is the last bit shifted out of the bottom.
+        self.sr.carry = shift != 0 && (value >> (shift - 1)) & 1 != 0;
         Ok(())
     }
 
-    fn exec_load(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_load(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let offset = (instr & 0xF) as u16;
         
         let address = self.registers[rs].wrapping_add(offset * 2); // Word-aligned
-        let value = self.read_memory(address);
-        
+        let value = self.read_memory(address)?;
+
         self.registers[rd] = value;
         self.registers[0] = 0;
         
         Ok(())
     }
 
-    fn exec_store(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_store(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let rs = ((instr >> 4) & 0xF) as usize;
         let offset = (instr & 0xF) as u16;
         
         let address = self.registers[rs].wrapping_add(offset * 2);
-        self.write_memory(address, self.registers[rd]);
-        
+        self.write_memory(address, self.registers[rd])?;
+
         Ok(())
     }
 
-    fn exec_loadi(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_loadi(&mut self, instr: u16) -> Result<(), VmError> {
         let rd = ((instr >> 8) & 0xF) as usize;
         let imm = (instr & 0xFF) as u16;
         
@@ -350,13 +602,13 @@ impl CVEREVM {
         Ok(())
     }
 
-    fn exec_jmp(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_jmp(&mut self, instr: u16) -> Result<(), VmError> {
         let target = instr & 0xFFF;
         self.pc = target;
         Ok(())
     }
 
-    fn exec_beq(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_beq(&mut self, instr: u16) -> Result<(), VmError> {
         let rc = ((instr >> 8) & 0xF) as usize;
         let offset = (instr & 0xFF) as i8; // Signed offset
         
@@ -367,19 +619,22 @@ impl CVEREVM {
         Ok(())
     }
 
-    fn exec_bne_or_extended(&mut self, instr: u16) -> Result<(), String> {
+    fn exec_bne_or_extended(&mut self, instr: u16) -> Result<(), VmError> {
         // Check for HALT
         if instr == 0xFFFF {
             self.halted = true;
             return Ok(());
         }
         
-        // Check for extended instructions
+        // Check for extended instructions: the reserved opcodes 0xF0-0xF3 are
+        // syscall/trap gates whose low byte selects a registered handler. As a
+        // consequence BNE R0-R3 is not encodable (the assembler uses R4 and up
+        // for branch conditions); 0xF4-0xFF remain available to BNE.
         let extended_op = (instr >> 8) & 0xFF;
         if extended_op >= 0xF0 && extended_op <= 0xF3 {
-            return Ok(()); // Extended instructions not fully implemented
+            return self.exec_syscall((instr & 0xFF) as u8, instr);
         }
-        
+
         // BNE instruction
         let rc = ((instr >> 8) & 0xF) as usize;
         let offset = (instr & 0xFF) as i8;
@@ -391,6 +646,79 @@ impl CVEREVM {
         Ok(())
     }
 
+    /// Per-opcode cycle cost: memory ops and control transfers cost more than
+    /// register-only operations.
+    fn instruction_cost(opcode: u16) -> u64 {
+        match opcode {
+            0xA | 0xB => 3,       // LOAD / STORE
+            0xD | 0xE | 0xF => 2, // JMP / BEQ / BNE & extended
+            _ => 1,               // register ops, immediates, NOP
+        }
+    }
+
+    /// Queue an interrupt to be serviced at the next `step` (for bus devices).
+    pub fn raise_interrupt(&mut self, vector: u16) {
+        self.interrupts.push_back(vector);
+    }
+
+    /// RETI (opcode 0x0, selector 0xA): restore SR then PC from the stack,
+    /// re-enabling interrupts via the saved SR's I flag.
+    fn exec_reti(&mut self) -> Result<(), VmError> {
+        let sr = self.pop_word()?;
+        self.sr = StatusFlags::from_u16(sr);
+        self.pc = self.pop_word()?;
+        Ok(())
+    }
+
+    /// If interrupts are enabled and one is pending, save PC and SR to the
+    /// stack, jump to the handler vector, and mask further interrupts.
+    fn service_interrupt(&mut self) -> Result<(), VmError> {
+        if !self.sr.interrupt_enable {
+            return Ok(());
+        }
+        if let Some(vector) = self.interrupts.pop_front() {
+            self.push_word(self.pc)?;
+            self.push_word(self.sr.to_u16())?;
+            self.sr.interrupt_enable = false;
+            self.pc = vector;
+        }
+        Ok(())
+    }
+
+    /// Push a word onto the downward-growing stack.
+    fn push_word(&mut self, value: u16) -> Result<(), VmError> {
+        self.sp = self.sp.wrapping_sub(2);
+        self.write_memory(self.sp, value)
+    }
+
+    /// Pop a word from the stack.
+    fn pop_word(&mut self) -> Result<u16, VmError> {
+        let value = self.read_memory(self.sp)?;
+        self.sp = self.sp.wrapping_add(2);
+        Ok(value)
+    }
+
+    /// Dispatch a syscall: pass R1-R3 to the registered handler and write the
+    /// return words back into R1/R2 (R0 stays hardwired to 0).
+    fn exec_syscall(&mut self, num: u8, instr: u16) -> Result<(), VmError> {
+        let args = SyscallArgs {
+            a0: self.registers[1],
+            a1: self.registers[2],
+            a2: self.registers[3],
+        };
+        let outcome = match self.syscalls.get_mut(&num) {
+            Some(handler) => handler.handle(args)?,
+            None => return Err(VmError::InvalidOpcode { opcode: instr }),
+        };
+        self.registers[1] = outcome.ret0;
+        self.registers[2] = outcome.ret1;
+        self.registers[0] = 0; // R0 is hardwired to 0
+        if outcome.action == SyscallAction::Halt {
+            self.halted = true;
+        }
+        Ok(())
+    }
+
     /// Reset VM to initial state
     pub fn reset(&mut self) {
         self.registers = [0; 16];
@@ -426,9 +754,10 @@ impl fmt::Display for CVEREVM {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "=== CVERE VM State ===")?;
         writeln!(f, "PC: 0x{:04X}  SP: 0x{:04X}  LR: 0x{:04X}", self.pc, self.sp, self.lr)?;
-        writeln!(f, "SR: Z={} N={} C={} V={}", 
-            self.sr.zero as u8, self.sr.negative as u8, 
-            self.sr.carry as u8, self.sr.overflow as u8)?;
+        writeln!(f, "SR: Z={} N={} C={} V={} I={}",
+            self.sr.zero as u8, self.sr.negative as u8,
+            self.sr.carry as u8, self.sr.overflow as u8,
+            self.sr.interrupt_enable as u8)?;
         writeln!(f, "Cycles: {}  Halted: {}", self.cycle_count, self.halted)?;
         writeln!(f, "\nRegisters:")?;
         for i in 0..16 {
@@ -467,18 +796,144 @@ mod tests {
     #[test]
     fn test_loop() {
         let mut vm = CVEREVM::new();
+        // The branch register is R4: BNE R0-R3 would collide with the trap
+        // gates at 0xF0-0xF3, so those register numbers can't drive a branch.
         let program = vec![
             0xC100, // LOADI R1, 0x00
             0xC20A, // LOADI R2, 0x0A
             0x2101, // ADDI R1, 0x01
-            0x3321, // SUB R3, R2, R1
-            0xF3FD, // BNE R3, -3
+            0x3421, // SUB R4, R2, R1
+            0xF4FD, // BNE R4, -3
             0xFFFF, // HALT
         ];
         
         vm.load_program(&program, 0);
         vm.run(100).unwrap();
-        
+
         assert_eq!(vm.registers[1], 10);
     }
+
+    #[test]
+    fn test_ext_mul() {
+        let mut vm = CVEREVM::new();
+        let program = vec![
+            0xC105, // LOADI R1, 0x05
+            0xC203, // LOADI R2, 0x03
+            0x0112, // MUL R1, R2  (R1 = R1 * R2)
+            0xFFFF, // HALT
+        ];
+
+        vm.load_program(&program, 0);
+        vm.run(100).unwrap();
+
+        assert_eq!(vm.registers[1], 15);
+    }
+
+    #[test]
+    fn test_ext_divide_by_zero() {
+        let mut vm = CVEREVM::new();
+        let program = vec![
+            0xC105, // LOADI R1, 0x05
+            0xC200, // LOADI R2, 0x00
+            0x0312, // DIV R1, R2
+            0xFFFF, // HALT
+        ];
+
+        vm.load_program(&program, 0);
+        assert_eq!(vm.run(100), Err(VmError::DivideByZero));
+    }
+
+    #[test]
+    fn test_reti_returns_from_interrupt() {
+        let mut vm = CVEREVM::new();
+        let program = vec![
+            0x2101, // ADDI R1, 0x01
+            0xFFFF, // HALT
+        ];
+        vm.load_program(&program, 0);
+        vm.load_program(&[0x0A00], 0x10); // RETI at the handler vector
+        vm.raise_interrupt(0x10);
+
+        // The step takes the interrupt, runs the handler, and RETIs back to PC 0
+        // with interrupts re-enabled from the saved SR.
+        vm.step().unwrap();
+        assert_eq!(vm.pc, 0);
+        assert!(vm.sr.interrupt_enable);
+
+        vm.run(100).unwrap();
+        assert_eq!(vm.registers[1], 1);
+        assert!(vm.halted);
+    }
+
+    #[test]
+    fn test_read_watchpoint_is_resumable() {
+        use crate::bus::Bus;
+        use crate::debugger::WatchKind;
+
+        let mut vm = CVEREVM::new();
+        // Seed a value and watch reads of it.
+        vm.bus.write_u16(0x20, 0x00AB).unwrap();
+        vm.debugger.add_watchpoint(0x20, WatchKind::Read);
+
+        let program = vec![
+            0xC220, // LOADI R2, 0x20  (address)
+            0xA120, // LOAD R1, [R2]
+            0xFFFF, // HALT
+        ];
+        vm.load_program(&program, 0);
+
+        // The LOAD completes (R1 is updated) before the watchpoint stops us.
+        assert_eq!(vm.run(100), Err(VmError::Breakpoint));
+        assert_eq!(vm.registers[1], 0x00AB);
+        assert!(!vm.halted);
+
+        // Resuming continues to HALT.
+        vm.run(100).unwrap();
+        assert!(vm.halted);
+    }
+
+    #[test]
+    fn test_syscall_returns_value_in_r1() {
+        use crate::trap::ReadWordHandler;
+
+        let mut vm = CVEREVM::new();
+        let mut reader = ReadWordHandler::new();
+        reader.queue(0x1234);
+        vm.register_syscall(0x03, Box::new(reader));
+
+        let program = vec![
+            0xF003, // trap: SC_READ (syscall 0x03)
+            0xFFFF, // HALT
+        ];
+        vm.load_program(&program, 0);
+        vm.run(100).unwrap();
+
+        assert_eq!(vm.registers[1], 0x1234);
+    }
+
+    #[test]
+    fn test_mmio_console_write() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        use crate::bus::{ConsoleDevice, MappedBus, RamBus};
+
+        let sink = Rc::new(RefCell::new(Vec::new()));
+        let mut mapped = MappedBus::new(Box::new(RamBus::new(65536)));
+        mapped.map(0xFF00, 0xFF01, Box::new(ConsoleDevice::with_sink(sink.clone())));
+        let mut vm = CVEREVM::with_bus(Box::new(mapped));
+
+        let program = vec![
+            0xC2FF, // LOADI R2, 0xFF  (sign-extends to 0xFFFF)
+            0xC308, // LOADI R3, 0x08
+            0x8223, // SHL R2, R2, R3  (R2 = 0xFF00)
+            0xC141, // LOADI R1, 0x41  ('A')
+            0xB120, // STORE R1, [R2]
+            0xFFFF, // HALT
+        ];
+        vm.load_program(&program, 0);
+        vm.run(100).unwrap();
+
+        assert_eq!(&*sink.borrow(), b"A");
+    }
 }
\ No newline at end of file